@@ -9,6 +9,8 @@
 //!
 //! This crate exports a single type [`GraphMl`] which combines a build-pattern for configuration and provides creating strings ([`GraphMl::to_string`]) and writing to writers ([`GraphMl::to_writer`]).
 //!
+//! It also exports [`GraphMlReader`], which reads a GraphML document back into a [`petgraph::graph::Graph`].
+//!
 //! # Usage
 //!
 //! Add this to your `Cargo.toml`:
@@ -59,10 +61,10 @@
 //!     <node id="n2">
 //!       <data key="weight">2</data>
 //!     </node>
-//!     <edge id="e0" source="n0" target="n1" />
-//!     <edge id="e1" source="n1" target="n2" />
+//!     <edge id="e0" source="n0" target="n1"/>
+//!     <edge id="e1" source="n1" target="n2"/>
 //!   </graph>
-//!   <key id="weight" for="node" attr.name="weight" attr.type="string" />
+//!   <key id="weight" for="node" attr.name="weight" attr.type="string"/>
 //! </graphml>"#
 //! );
 //! # }
@@ -71,6 +73,7 @@
 //! [`GraphMl`]: https://docs.rs/petgraph-graphml/*/petgraph_graphml/struct.GraphMl.html
 //! [`GraphMl::to_string`]: https://docs.rs/petgraph-graphml/*/petgraph_graphml/struct.GraphMl.html#method.to_string
 //! [`GraphMl::to_writer`]: https://docs.rs/petgraph-graphml/*/petgraph_graphml/struct.GraphMl.html#method.to_writer
+//! [`GraphMlReader`]: https://docs.rs/petgraph-graphml/*/petgraph_graphml/struct.GraphMlReader.html
 //! [graphmlwebsite]: http://graphml.graphdrawing.org/
 //! [petgraph]: https://docs.rs/petgraph/
 //!
@@ -87,28 +90,118 @@
     variant_size_differences
 )]
 
-extern crate petgraph;
-extern crate xml;
+mod reader;
+
+pub use crate::reader::{GraphMlError, GraphMlReader};
 
 use petgraph::visit::{
     EdgeRef, GraphProp, IntoEdgeReferences, IntoNodeReferences, NodeIndexable, NodeRef,
 };
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::Writer;
 use std::borrow::Cow;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::error::Error as StdError;
 use std::fmt::Debug;
 use std::io::{self, Cursor, Write};
 use std::string::ToString;
-use xml::common::XmlVersion;
-use xml::writer::events::XmlEvent;
-use xml::writer::{Error as XmlError, EventWriter, Result as WriterResult};
-use xml::EmitterConfig;
+
+type WriterResult<T> = Result<T, GraphMlWriteError>;
+
+/// Errors that can occur while writing a GraphML document.
+#[derive(Debug)]
+pub enum GraphMlWriteError {
+    /// Writing to the underlying writer failed, or the document could not be XML-encoded.
+    Xml(Box<quick_xml::Error>),
+    /// The same attribute name was used with conflicting [`AttrType`]s across different
+    /// nodes/edges of the same kind. All nodes (or all edges) sharing an attribute name share
+    /// a single `<key>` declaration, so they must all agree on its `attr.type`.
+    ConflictingAttributeType {
+        /// The `attr.name` that was declared with two different types.
+        name: Cow<'static, str>,
+        /// `"node"` or `"edge"`, depending on which kind of attribute conflicted.
+        for_: &'static str,
+        /// The type the attribute was first declared with.
+        declared: AttrType,
+        /// The type it was then used with.
+        found: AttrType,
+    },
+}
+
+impl std::fmt::Display for GraphMlWriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GraphMlWriteError::Xml(err) => write!(f, "failed to write XML: {}", err),
+            GraphMlWriteError::ConflictingAttributeType {
+                name,
+                for_,
+                declared,
+                found,
+            } => write!(
+                f,
+                "attribute `{}` for {} was previously declared with type {:?}, but is now used with type {:?}",
+                name, for_, declared, found
+            ),
+        }
+    }
+}
+
+impl StdError for GraphMlWriteError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            GraphMlWriteError::Xml(err) => Some(err.as_ref()),
+            GraphMlWriteError::ConflictingAttributeType { .. } => None,
+        }
+    }
+}
+
+impl From<quick_xml::Error> for GraphMlWriteError {
+    fn from(err: quick_xml::Error) -> Self {
+        GraphMlWriteError::Xml(Box::new(err))
+    }
+}
 
 static NAMESPACE_URL: &str = "http://graphml.graphdrawing.org/xmlns";
 
+/// The type of an attribute value, written out as the `attr.type` of a GraphML `<key>` element.
+///
+/// This corresponds to the `AttrType` choices of the [GraphML primer][primer].
+///
+/// [primer]: http://graphml.graphdrawing.org/primer/graphml-primer.html#AttributesDefinition
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum AttrType {
+    /// `boolean`
+    Boolean,
+    /// `int`
+    Int,
+    /// `long`
+    Long,
+    /// `float`
+    Float,
+    /// `double`
+    Double,
+    /// `string`
+    String,
+}
+
+impl AttrType {
+    fn to_str(self) -> &'static str {
+        match self {
+            AttrType::Boolean => "boolean",
+            AttrType::Int => "int",
+            AttrType::Long => "long",
+            AttrType::Float => "float",
+            AttrType::Double => "double",
+            AttrType::String => "string",
+        }
+    }
+}
+
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 struct Attribute {
     name: Cow<'static, str>,
     for_: For,
+    attr_type: AttrType,
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
@@ -126,7 +219,77 @@ impl For {
     }
 }
 
-type PrintWeights<W> = for<'a> Fn(&'a W) -> Vec<(Cow<'static, str>, Cow<'a, str>)>;
+#[allow(clippy::type_complexity)]
+type PrintWeights<W> = dyn for<'a> Fn(&'a W) -> Vec<(Cow<'static, str>, AttrType, Cow<'a, str>)>;
+
+/// Computes the GraphML id for a node or edge from its [`NodeRef`]/[`EdgeRef`].
+type IdFn<R> = dyn for<'a> Fn(&'a R) -> Cow<'a, str>;
+
+/// Replace any character that is not legal in an XML `Name` production with `_`, and make sure
+/// the result starts with a valid `NameStartChar` (inserting a leading `_` if necessary).
+fn sanitize_xml_name(raw: &str) -> String {
+    fn is_name_start_char(c: char) -> bool {
+        c.is_alphabetic() || c == '_' || c == ':'
+    }
+    fn is_name_char(c: char) -> bool {
+        c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':')
+    }
+
+    let mut sanitized: String = raw
+        .chars()
+        .map(|c| if is_name_char(c) { c } else { '_' })
+        .collect();
+    if sanitized.is_empty() {
+        sanitized.push('_');
+    }
+    if !sanitized.starts_with(is_name_start_char) {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+/// Insert `candidate` into `used`, appending a numeric suffix until the result is unique.
+fn dedup_id(candidate: String, used: &mut HashSet<String>) -> String {
+    if used.insert(candidate.clone()) {
+        return candidate;
+    }
+    let mut suffix: u64 = 1;
+    loop {
+        let attempt = format!("{}_{}", candidate, suffix);
+        if used.insert(attempt.clone()) {
+            return attempt;
+        }
+        suffix += 1;
+    }
+}
+
+/// An attribute computed from the whole graph plus the [`NodeRef`]/[`EdgeRef`] being visited,
+/// rather than just its weight. It takes the same arguments as the getters of petgraph's
+/// [`Dot::with_attr_getters`](https://docs.rs/petgraph/*/petgraph/dot/struct.Dot.html#method.with_attr_getters),
+/// but returns key-type-value triples instead of a single `String`.
+#[allow(clippy::type_complexity)]
+type PrintWeightsWithGraph<G, R> = dyn Fn(G, R) -> Vec<(Cow<'static, str>, AttrType, String)>;
+
+/// Computes the Neo4j/APOC node labels for a [`NodeRef`].
+type NodeLabelFn<R> = dyn Fn(&R) -> Vec<String>;
+
+/// Computes the Neo4j/APOC edge relationship type for an [`EdgeRef`].
+type EdgeTypeFn<R> = dyn Fn(&R) -> String;
+
+/// Output configuration for [`GraphMl`], mirroring petgraph's own [`Dot::with_config`].
+///
+/// [`Dot::with_config`]: https://docs.rs/petgraph/*/petgraph/dot/struct.Dot.html#method.with_config
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Config {
+    /// Do not emit the auto-generated `id` attribute on `<edge>` elements.
+    NoEdgeIds,
+    /// Do not emit the `edgedefault` attribute on the `<graph>` element.
+    NoEdgeDefault,
+    /// Declare `edgedefault="directed"`, regardless of what the graph itself reports.
+    EdgeDirected,
+    /// Declare `edgedefault="undirected"`, regardless of what the graph itself reports.
+    EdgeUndirected,
+}
 
 /// GraphML output printer
 ///
@@ -140,6 +303,14 @@ where
     pretty_print: bool,
     export_edges: Option<Box<PrintWeights<G::EdgeWeight>>>,
     export_nodes: Option<Box<PrintWeights<G::NodeWeight>>>,
+    export_edges_with_graph: Option<Box<PrintWeightsWithGraph<G, G::EdgeRef>>>,
+    export_nodes_with_graph: Option<Box<PrintWeightsWithGraph<G, G::NodeRef>>>,
+    attribute_defaults: HashMap<(Cow<'static, str>, For), Cow<'static, str>>,
+    config: HashSet<Config>,
+    node_id: Option<Box<IdFn<G::NodeRef>>>,
+    edge_id: Option<Box<IdFn<G::EdgeRef>>>,
+    node_labels: Option<Box<NodeLabelFn<G::NodeRef>>>,
+    edge_type: Option<Box<EdgeTypeFn<G::EdgeRef>>>,
 }
 
 impl<G> GraphMl<G>
@@ -148,14 +319,43 @@ where
     G: IntoNodeReferences,
     G: IntoEdgeReferences,
     G: NodeIndexable,
+    G: Copy,
 {
     /// Create a new GraphML printer for the graph.
     pub fn new(graph: G) -> Self {
+        Self::with_config(graph, &[])
+    }
+
+    /// Create a new GraphML printer for the graph with the given [`Config`] flags enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate petgraph;
+    /// # extern crate petgraph_graphml;
+    /// # use petgraph::Graph;
+    /// # use petgraph_graphml::{Config, GraphMl};
+    /// # fn main() {
+    /// let mut graph = Graph::<&str, &str>::new();
+    /// graph.add_node("petgraph");
+    /// let graphml = GraphMl::with_config(&graph, &[Config::NoEdgeDefault]);
+    /// assert!(!graphml.to_string().contains("edgedefault"));
+    /// # }
+    /// ```
+    pub fn with_config(graph: G, config: &[Config]) -> Self {
         Self {
             graph,
             pretty_print: true,
             export_edges: None,
             export_nodes: None,
+            export_edges_with_graph: None,
+            export_nodes_with_graph: None,
+            attribute_defaults: HashMap::new(),
+            config: config.iter().copied().collect(),
+            node_id: None,
+            edge_id: None,
+            node_labels: None,
+            edge_type: None,
         }
     }
 
@@ -180,7 +380,7 @@ where
         G::EdgeWeight: ToString,
     {
         self.export_edge_weights(Box::new(|edge| {
-            vec![("weight".into(), edge.to_string().into())]
+            vec![("weight".into(), AttrType::String, edge.to_string().into())]
         }))
     }
 
@@ -188,7 +388,7 @@ where
     ///
     /// This uses a custom conversion function.
     /// Each edge can be converted into an arbitray number of attributes.
-    /// Each attribute is a key-value pair, represented as tuple.
+    /// Each attribute is a key-type-value triple.
     ///
     /// Once set this option cannot be disabled anymore.
     ///
@@ -201,7 +401,7 @@ where
     /// # extern crate petgraph;
     /// # extern crate petgraph_graphml;
     /// # use petgraph::Graph;
-    /// # use petgraph_graphml::GraphMl;
+    /// # use petgraph_graphml::{AttrType, GraphMl};
     /// # fn make_graph() -> Graph<(), (String, u32)> {
     /// #     Graph::new()
     /// # }
@@ -211,14 +411,12 @@ where
     ///     .export_edge_weights(Box::new(|edge| {
     ///         let &(ref s, i) = edge;
     ///         vec![
-    ///             ("str attr".into(), s[..].into()),
-    ///             ("int attr".into(), i.to_string().into()),
+    ///             ("str attr".into(), AttrType::String, s[..].into()),
+    ///             ("int attr".into(), AttrType::Int, i.to_string().into()),
     ///         ]
     ///     }));
     /// # }
     /// ```
-    ///
-    /// Currently only string attribute types are supported.
     pub fn export_edge_weights(mut self, edge_weight: Box<PrintWeights<G::EdgeWeight>>) -> Self {
         self.export_edges = Some(edge_weight);
         self
@@ -237,7 +435,7 @@ where
         G::NodeWeight: ToString,
     {
         self.export_node_weights(Box::new(|node| {
-            vec![("weight".into(), node.to_string().into())]
+            vec![("weight".into(), AttrType::String, node.to_string().into())]
         }))
     }
 
@@ -245,7 +443,7 @@ where
     ///
     /// This uses a custom conversion function.
     /// Each node can be converted into an arbitray number of attributes.
-    /// Each attribute is a key-value pair, represented as tuple.
+    /// Each attribute is a key-type-value triple.
     ///
     /// Once set this option cannot be disabled anymore.
     ///
@@ -258,7 +456,7 @@ where
     /// # extern crate petgraph;
     /// # extern crate petgraph_graphml;
     /// # use petgraph::Graph;
-    /// # use petgraph_graphml::GraphMl;
+    /// # use petgraph_graphml::{AttrType, GraphMl};
     /// # fn make_graph() -> Graph<(String, u32), ()> {
     /// #     Graph::new()
     /// # }
@@ -268,44 +466,160 @@ where
     ///     .export_node_weights(Box::new(|node| {
     ///         let &(ref s, i) = node;
     ///         vec![
-    ///             ("str attr".into(), s[..].into()),
-    ///             ("int attr".into(), i.to_string().into()),
+    ///             ("str attr".into(), AttrType::String, s[..].into()),
+    ///             ("int attr".into(), AttrType::Int, i.to_string().into()),
     ///         ]
     ///     }));
     /// # }
     /// ```
-    ///
-    /// Currently only string attribute types are supported.
     pub fn export_node_weights(mut self, node_weight: Box<PrintWeights<G::NodeWeight>>) -> Self {
         self.export_nodes = Some(node_weight);
         self
     }
 
-    /// Create a string with the GraphML content.
-    pub fn to_string(&self) -> String {
-        let mut buff = Cursor::new(Vec::new());
-        self.to_writer(&mut buff)
-            .expect("Writing to a Cursor should never create IO errors.");
-        String::from_utf8(buff.into_inner()).unwrap()
+    /// Export edge attributes computed from the whole graph.
+    ///
+    /// Unlike [`export_edge_weights`](Self::export_edge_weights), the conversion function
+    /// receives the graph together with the [`EdgeRef`] being visited, so an attribute can be
+    /// computed from the edge's endpoints and not just its weight.
+    ///
+    /// Once set this option cannot be disabled anymore.
+    pub fn export_edge_weights_with_graph(
+        mut self,
+        edge_weight: Box<PrintWeightsWithGraph<G, G::EdgeRef>>,
+    ) -> Self {
+        self.export_edges_with_graph = Some(edge_weight);
+        self
+    }
+
+    /// Export node attributes computed from the whole graph.
+    ///
+    /// Unlike [`export_node_weights`](Self::export_node_weights), the conversion function
+    /// receives the graph together with the [`NodeRef`] being visited, so an attribute can be
+    /// computed from e.g. the node's degree or its neighbors, and not just its weight.
+    ///
+    /// Once set this option cannot be disabled anymore.
+    ///
+    /// # Example
+    ///
+    /// Emit each node's out-degree as an attribute.
+    ///
+    /// ```
+    /// # extern crate petgraph;
+    /// # extern crate petgraph_graphml;
+    /// # use petgraph::visit::NodeRef;
+    /// # use petgraph::Graph;
+    /// # use petgraph_graphml::{AttrType, GraphMl};
+    /// # fn main() {
+    /// let mut graph = Graph::<&str, ()>::new();
+    /// let a = graph.add_node("a");
+    /// let b = graph.add_node("b");
+    /// graph.add_edge(a, b, ());
+    /// let graphml = GraphMl::new(&graph).export_node_weights_with_graph(Box::new(|graph, node| {
+    ///     let out_degree = graph.neighbors(node.id()).count();
+    ///     vec![("out_degree".into(), AttrType::Int, out_degree.to_string())]
+    /// }));
+    /// # let _ = graphml.to_string();
+    /// # }
+    /// ```
+    pub fn export_node_weights_with_graph(
+        mut self,
+        node_weight: Box<PrintWeightsWithGraph<G, G::NodeRef>>,
+    ) -> Self {
+        self.export_nodes_with_graph = Some(node_weight);
+        self
+    }
+
+    /// Set a default value for a node attribute.
+    ///
+    /// GraphML consumers use this value whenever a `<data>` element for the attribute is
+    /// missing from a `<node>`. The default is emitted as a `<default>` child of the
+    /// attribute's `<key>` declaration.
+    pub fn node_attribute_default<S1, S2>(mut self, name: S1, default: S2) -> Self
+    where
+        S1: Into<Cow<'static, str>>,
+        S2: Into<Cow<'static, str>>,
+    {
+        self.attribute_defaults
+            .insert((name.into(), For::Node), default.into());
+        self
+    }
+
+    /// Set a default value for an edge attribute.
+    ///
+    /// See [`node_attribute_default`](#method.node_attribute_default) for details.
+    pub fn edge_attribute_default<S1, S2>(mut self, name: S1, default: S2) -> Self
+    where
+        S1: Into<Cow<'static, str>>,
+        S2: Into<Cow<'static, str>>,
+    {
+        self.attribute_defaults
+            .insert((name.into(), For::Edge), default.into());
+        self
+    }
+
+    /// Use a custom id for each node, instead of the auto-generated `n0`, `n1`, ....
+    ///
+    /// Ids are sanitized to be legal XML `Name`s and de-duplicated by appending a numeric
+    /// suffix, so the output stays parseable by [`GraphMlReader`](crate::GraphMlReader) even if
+    /// the closure returns invalid or colliding ids.
+    ///
+    /// Once set this option cannot be disabled anymore.
+    pub fn node_id(mut self, node_id: Box<IdFn<G::NodeRef>>) -> Self {
+        self.node_id = Some(node_id);
+        self
+    }
+
+    /// Use a custom id for each edge, instead of the auto-generated `e0`, `e1`, ....
+    ///
+    /// See [`node_id`](Self::node_id) for details on sanitization and de-duplication.
+    /// Has no effect if [`Config::NoEdgeIds`] is set.
+    ///
+    /// Once set this option cannot be disabled anymore.
+    pub fn edge_id(mut self, edge_id: Box<IdFn<G::EdgeRef>>) -> Self {
+        self.edge_id = Some(edge_id);
+        self
+    }
+
+    /// Export each node's Neo4j labels, for use with `apoc.import.graphml`.
+    ///
+    /// Emits a reserved `labels` attribute (`for="node"`) whose value is the `:`-delimited
+    /// label string APOC expects, e.g. `:Person:Employee`. Regular node properties can still be
+    /// exported alongside it via [`export_node_weights`](Self::export_node_weights) and friends.
+    ///
+    /// Once set this option cannot be disabled anymore.
+    pub fn node_labels(mut self, node_labels: Box<NodeLabelFn<G::NodeRef>>) -> Self {
+        self.node_labels = Some(node_labels);
+        self
+    }
+
+    /// Export each edge's Neo4j relationship type, for use with `apoc.import.graphml`.
+    ///
+    /// Emits a reserved `label` attribute (`for="edge"`) containing the relationship type.
+    ///
+    /// Once set this option cannot be disabled anymore.
+    pub fn edge_type(mut self, edge_type: Box<EdgeTypeFn<G::EdgeRef>>) -> Self {
+        self.edge_type = Some(edge_type);
+        self
     }
 
     /// Write the GraphML file to the given writer.
+    ///
+    /// This streams events directly to `writer` as they are produced, without ever buffering
+    /// the whole document in memory.
     pub fn to_writer<W>(&self, writer: W) -> io::Result<()>
     where
         W: Write,
     {
-        let mut writer = EventWriter::new_with_config(
-            writer,
-            EmitterConfig::new().perform_indent(self.pretty_print),
-        );
-        match self.emit_graphml(&mut writer) {
-            Ok(()) => Ok(()),
-            Err(XmlError::Io(ioerror)) => Err(ioerror),
-            _ => panic!(""),
-        }
+        let mut writer = if self.pretty_print {
+            Writer::new_with_indent(writer, b' ', 2)
+        } else {
+            Writer::new(writer)
+        };
+        self.emit_graphml(&mut writer).map_err(io::Error::other)
     }
 
-    fn emit_graphml<W>(&self, writer: &mut EventWriter<W>) -> WriterResult<()>
+    fn emit_graphml<W>(&self, writer: &mut Writer<W>) -> WriterResult<()>
     where
         W: Write,
     {
@@ -314,110 +628,259 @@ where
         let mut attributes: HashSet<Attribute> = HashSet::new();
 
         // XML/GraphML boilerplate
-        writer.write(XmlEvent::StartDocument {
-            version: XmlVersion::Version10,
-            encoding: Some("UTF-8"),
-            standalone: None,
-        })?;
-        writer.write(XmlEvent::start_element("graphml").attr("xmlns", NAMESPACE_URL))?;
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+        let mut graphml_start = BytesStart::new("graphml");
+        graphml_start.push_attribute(("xmlns", NAMESPACE_URL));
+        writer.write_event(Event::Start(graphml_start))?;
 
         // emit graph with nodes/edges and possibly weights
         self.emit_graph(writer, &mut attributes)?;
         // Emit <key> tags for all the attributes
         self.emit_keys(writer, &attributes)?;
 
-        writer.write(XmlEvent::end_element())?; // end graphml
+        writer.write_event(Event::End(BytesEnd::new("graphml")))?;
         Ok(())
     }
 
     fn emit_graph<W>(
         &self,
-        writer: &mut EventWriter<W>,
+        writer: &mut Writer<W>,
         attributes: &mut HashSet<Attribute>,
     ) -> WriterResult<()>
     where
         W: Write,
     {
-        // convenience function to turn a NodeId into a String
-        let node2str_id = |node: G::NodeId| -> String { format!("n{}", self.graph.to_index(node)) };
-        // Emit an attribute for either node or edge
-        // This will also keep track of updating the global attributes list
-        let mut emit_attribute = |writer: &mut EventWriter<_>,
-                                  name: Cow<'static, str>,
-                                  data: &str,
-                                  for_: For|
-         -> WriterResult<()> {
-            writer.write(XmlEvent::start_element("data").attr("key", &*name))?;
-            attributes.insert(Attribute { name, for_ });
-            writer.write(XmlEvent::characters(data))?;
-            writer.write(XmlEvent::end_element()) // end data
-        };
+        // Compute every node's final, sanitized, unique id up front (keyed by its graph index),
+        // so that edges can look up their endpoints' ids without needing a `NodeRef` of their own.
+        let mut used_node_ids: HashSet<String> = HashSet::new();
+        let node_ids: HashMap<usize, String> = self
+            .graph
+            .node_references()
+            .map(|node| {
+                let idx = self.graph.to_index(node.id());
+                let raw_id = match self.node_id {
+                    Some(ref node_id) => node_id(&node).into_owned(),
+                    None => format!("n{}", idx),
+                };
+                (
+                    idx,
+                    dedup_id(sanitize_xml_name(&raw_id), &mut used_node_ids),
+                )
+            })
+            .collect();
 
-        // Each graph needs a default edge type
-        writer.write(XmlEvent::start_element("graph").attr(
-            "edgedefault",
-            if self.graph.is_directed() {
-                "directed"
+        // Each graph needs a default edge type, unless suppressed via `Config::NoEdgeDefault`
+        let mut graph_start = BytesStart::new("graph");
+        if !self.config.contains(&Config::NoEdgeDefault) {
+            let directed = if self.config.contains(&Config::EdgeUndirected) {
+                false
+            } else if self.config.contains(&Config::EdgeDirected) {
+                true
             } else {
-                "undirected"
-            },
-        ))?;
+                self.graph.is_directed()
+            };
+            graph_start.push_attribute((
+                "edgedefault",
+                if directed { "directed" } else { "undirected" },
+            ));
+        }
+        writer.write_event(Event::Start(graph_start))?;
 
         // Emit nodes
         for node in self.graph.node_references() {
-            writer.write(XmlEvent::start_element("node").attr("id", &*node2str_id(node.id())))?;
-            // Print weights
+            let id = &node_ids[&self.graph.to_index(node.id())];
+
+            let mut datas: Vec<(Cow<'static, str>, AttrType, String)> = Vec::new();
             if let Some(ref node_labels) = self.export_nodes {
-                let datas = node_labels(&node.weight());
-                for (name, data) in datas {
-                    emit_attribute(writer, name, &*data, For::Node)?;
+                datas.extend(
+                    node_labels(node.weight())
+                        .into_iter()
+                        .map(|(name, attr_type, data)| (name, attr_type, data.into_owned())),
+                );
+            }
+            if let Some(ref node_labels) = self.export_nodes_with_graph {
+                datas.extend(node_labels(self.graph, node));
+            }
+            if let Some(ref node_labels) = self.node_labels {
+                let labels = node_labels(&node);
+                datas.push((
+                    "labels".into(),
+                    AttrType::String,
+                    format!(":{}", labels.join(":")),
+                ));
+            }
+
+            let mut node_start = BytesStart::new("node");
+            node_start.push_attribute(("id", id.as_str()));
+            if datas.is_empty() {
+                writer.write_event(Event::Empty(node_start))?;
+            } else {
+                writer.write_event(Event::Start(node_start))?;
+                for (name, attr_type, data) in datas {
+                    Self::emit_data_attribute(
+                        writer,
+                        attributes,
+                        name,
+                        attr_type,
+                        &data,
+                        For::Node,
+                    )?;
                 }
+                writer.write_event(Event::End(BytesEnd::new("node")))?;
             }
-            writer.write(XmlEvent::end_element())?; // end node
         }
 
         // Emit edges
+        let mut used_edge_ids: HashSet<String> = HashSet::new();
         for (i, edge) in self.graph.edge_references().enumerate() {
-            writer.write(
-                XmlEvent::start_element("edge")
-                    .attr("id", &format!("e{}", i))
-                    .attr("source", &*node2str_id(edge.source()))
-                    .attr("target", &*node2str_id(edge.target())),
-            )?;
-            // Print weights
+            let source_id = node_ids[&self.graph.to_index(edge.source())].clone();
+            let target_id = node_ids[&self.graph.to_index(edge.target())].clone();
+
+            let edge_id = if !self.config.contains(&Config::NoEdgeIds) {
+                let raw_id = match self.edge_id {
+                    Some(ref edge_id) => edge_id(&edge).into_owned(),
+                    None => format!("e{}", i),
+                };
+                Some(dedup_id(sanitize_xml_name(&raw_id), &mut used_edge_ids))
+            } else {
+                None
+            };
+
+            let mut datas: Vec<(Cow<'static, str>, AttrType, String)> = Vec::new();
             if let Some(ref edge_labels) = self.export_edges {
-                let datas = edge_labels(&edge.weight());
-                for (name, data) in datas {
-                    emit_attribute(writer, name, &*data, For::Edge)?;
+                datas.extend(
+                    edge_labels(edge.weight())
+                        .into_iter()
+                        .map(|(name, attr_type, data)| (name, attr_type, data.into_owned())),
+                );
+            }
+            if let Some(ref edge_labels) = self.export_edges_with_graph {
+                datas.extend(edge_labels(self.graph, edge));
+            }
+            if let Some(ref edge_type) = self.edge_type {
+                datas.push(("label".into(), AttrType::String, edge_type(&edge)));
+            }
+
+            let mut edge_start = BytesStart::new("edge");
+            if let Some(ref edge_id) = edge_id {
+                edge_start.push_attribute(("id", edge_id.as_str()));
+            }
+            edge_start.push_attribute(("source", source_id.as_str()));
+            edge_start.push_attribute(("target", target_id.as_str()));
+
+            if datas.is_empty() {
+                writer.write_event(Event::Empty(edge_start))?;
+            } else {
+                writer.write_event(Event::Start(edge_start))?;
+                for (name, attr_type, data) in datas {
+                    Self::emit_data_attribute(
+                        writer,
+                        attributes,
+                        name,
+                        attr_type,
+                        &data,
+                        For::Edge,
+                    )?;
                 }
+                writer.write_event(Event::End(BytesEnd::new("edge")))?;
             }
-            writer.write(XmlEvent::end_element())?; // end edge
         }
-        writer.write(XmlEvent::end_element()) // end graph
+        writer.write_event(Event::End(BytesEnd::new("graph")))?;
+        Ok(())
+    }
+
+    /// Emit a `<data>` element for either a node or an edge, updating `attributes` so
+    /// `emit_keys` can later declare the corresponding `<key>`.
+    fn emit_data_attribute<W>(
+        writer: &mut Writer<W>,
+        attributes: &mut HashSet<Attribute>,
+        name: Cow<'static, str>,
+        attr_type: AttrType,
+        data: &str,
+        for_: For,
+    ) -> WriterResult<()>
+    where
+        W: Write,
+    {
+        if let Some(existing) = attributes
+            .iter()
+            .find(|attr| attr.name == name && attr.for_ == for_)
+        {
+            if existing.attr_type != attr_type {
+                return Err(GraphMlWriteError::ConflictingAttributeType {
+                    name,
+                    for_: for_.to_str(),
+                    declared: existing.attr_type,
+                    found: attr_type,
+                });
+            }
+        }
+
+        let mut data_start = BytesStart::new("data");
+        data_start.push_attribute(("key", name.as_ref()));
+        writer.write_event(Event::Start(data_start))?;
+        writer.write_event(Event::Text(BytesText::new(data)))?;
+        writer.write_event(Event::End(BytesEnd::new("data")))?;
+
+        attributes.insert(Attribute {
+            name,
+            for_,
+            attr_type,
+        });
+        Ok(())
     }
 
     fn emit_keys<W>(
         &self,
-        writer: &mut EventWriter<W>,
+        writer: &mut Writer<W>,
         attributes: &HashSet<Attribute>,
     ) -> WriterResult<()>
     where
         W: Write,
     {
         for attr in attributes {
-            writer.write(
-                XmlEvent::start_element("key")
-                    .attr("id", &*attr.name)
-                    .attr("for", attr.for_.to_str())
-                    .attr("attr.name", &*attr.name)
-                    .attr("attr.type", "string"),
-            )?;
-            writer.write(XmlEvent::end_element())?; // end key
+            let mut key_start = BytesStart::new("key");
+            key_start.push_attribute(("id", attr.name.as_ref()));
+            key_start.push_attribute(("for", attr.for_.to_str()));
+            key_start.push_attribute(("attr.name", attr.name.as_ref()));
+            key_start.push_attribute(("attr.type", attr.attr_type.to_str()));
+
+            if let Some(default) = self.attribute_defaults.get(&(attr.name.clone(), attr.for_)) {
+                writer.write_event(Event::Start(key_start))?;
+                writer.write_event(Event::Start(BytesStart::new("default")))?;
+                writer.write_event(Event::Text(BytesText::new(default)))?;
+                writer.write_event(Event::End(BytesEnd::new("default")))?;
+                writer.write_event(Event::End(BytesEnd::new("key")))?;
+            } else {
+                writer.write_event(Event::Empty(key_start))?;
+            }
         }
         Ok(())
     }
 }
 
+impl<G> std::fmt::Display for GraphMl<G>
+where
+    G: GraphProp,
+    G: IntoNodeReferences,
+    G: IntoEdgeReferences,
+    G: NodeIndexable,
+    G: Copy,
+{
+    /// # Panics
+    ///
+    /// Writing to an in-memory buffer cannot fail with an I/O error, but the export closures
+    /// can still yield a [`GraphMlWriteError::ConflictingAttributeType`] (the same attribute
+    /// name used with two different [`AttrType`]s) — this panics in that case. Use
+    /// [`to_writer`](GraphMl::to_writer) if you need to handle that conflict instead of panicking.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut buff = Cursor::new(Vec::new());
+        self.to_writer(&mut buff)
+            .expect("GraphMl::to_string: conflicting attribute type (see GraphMl::to_writer for a fallible alternative)");
+        f.write_str(&String::from_utf8(buff.into_inner()).unwrap())
+    }
+}
+
 impl<G> Debug for GraphMl<G>
 where
     G: Debug,
@@ -430,6 +893,20 @@ where
             .field("pretty_print", &self.pretty_print)
             .field("export_edges", &self.export_edges.is_some())
             .field("export_nodes", &self.export_nodes.is_some())
+            .field(
+                "export_edges_with_graph",
+                &self.export_edges_with_graph.is_some(),
+            )
+            .field(
+                "export_nodes_with_graph",
+                &self.export_nodes_with_graph.is_some(),
+            )
+            .field("attribute_defaults", &self.attribute_defaults)
+            .field("config", &self.config)
+            .field("node_id", &self.node_id.is_some())
+            .field("edge_id", &self.edge_id.is_some())
+            .field("node_labels", &self.node_labels.is_some())
+            .field("edge_type", &self.edge_type.is_some())
             .finish()
     }
 }
@@ -0,0 +1,362 @@
+//! Parsing GraphML documents back into a [`petgraph::graph::Graph`].
+
+use crate::AttrType;
+use petgraph::graph::{Graph, IndexType, NodeIndex};
+use petgraph::{Directed, EdgeType};
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::io::Read;
+use xml::reader::{Error as XmlReaderError, EventReader, XmlEvent};
+
+/// Errors that can occur while parsing a GraphML document.
+#[derive(Debug)]
+pub enum GraphMlError {
+    /// The underlying XML document was not well-formed.
+    Xml(Box<XmlReaderError>),
+    /// A required attribute was missing from an element.
+    MissingAttribute {
+        /// The element the attribute was expected on, e.g. `"node"`.
+        element: &'static str,
+        /// The name of the missing attribute, e.g. `"id"`.
+        attribute: &'static str,
+    },
+    /// A `for` attribute on a `<key>` element was neither `node`, `edge`, `graph` nor `all`.
+    InvalidKeyFor(String),
+    /// A `<data>` element referenced a `<key>` id that was never declared.
+    UnknownKey(String),
+    /// An `<edge>` referenced a node id that was never declared via `<node id="...">`.
+    UnknownNode(String),
+    /// The document's `<graph edgedefault="...">` does not match the requested [`EdgeType`].
+    EdgeDefaultMismatch {
+        /// The `edgedefault` the caller's graph type requires.
+        expected: &'static str,
+        /// The `edgedefault` that was actually found in the document.
+        found: &'static str,
+    },
+}
+
+impl fmt::Display for GraphMlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GraphMlError::Xml(err) => write!(f, "malformed XML: {}", err),
+            GraphMlError::MissingAttribute { element, attribute } => write!(
+                f,
+                "`<{}>` is missing the required `{}` attribute",
+                element, attribute
+            ),
+            GraphMlError::InvalidKeyFor(value) => {
+                write!(f, "`<key for=\"{}\">` is not one of node/edge/graph/all", value)
+            }
+            GraphMlError::UnknownKey(key) => {
+                write!(f, "`<data key=\"{}\">` references an undeclared key", key)
+            }
+            GraphMlError::UnknownNode(id) => {
+                write!(f, "`<edge>` references unknown node id \"{}\"", id)
+            }
+            GraphMlError::EdgeDefaultMismatch { expected, found } => write!(
+                f,
+                "expected a graph with edgedefault=\"{}\", but the document declares edgedefault=\"{}\"",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl StdError for GraphMlError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            GraphMlError::Xml(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<XmlReaderError> for GraphMlError {
+    fn from(err: XmlReaderError) -> Self {
+        GraphMlError::Xml(Box::new(err))
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum KeyFor {
+    Node,
+    Edge,
+    Graph,
+    All,
+}
+
+impl KeyFor {
+    fn parse(value: &str) -> Result<Self, GraphMlError> {
+        match value {
+            "node" => Ok(KeyFor::Node),
+            "edge" => Ok(KeyFor::Edge),
+            "graph" => Ok(KeyFor::Graph),
+            "all" => Ok(KeyFor::All),
+            other => Err(GraphMlError::InvalidKeyFor(other.to_owned())),
+        }
+    }
+
+    fn applies_to(self, scope: KeyFor) -> bool {
+        self == scope || self == KeyFor::All
+    }
+}
+
+struct KeyDecl {
+    for_: KeyFor,
+    attr_name: String,
+    #[allow(dead_code)] // kept for symmetry with the writer; not needed to resolve values yet
+    attr_type: AttrType,
+    default: Option<String>,
+}
+
+type NodeWeightFn<N> = dyn Fn(&HashMap<String, String>) -> N;
+type EdgeWeightFn<E> = dyn Fn(&HashMap<String, String>) -> E;
+
+/// GraphML reader / importer.
+///
+/// Builds a [`petgraph::graph::Graph`] from a GraphML document, the counterpart to
+/// [`GraphMl`](crate::GraphMl) on the writing side. The caller supplies closures that turn the
+/// `<data>` elements collected for a node or edge (keyed by `attr.name`, not by the raw `<key>`
+/// id) into the desired `NodeWeight`/`EdgeWeight`.
+pub struct GraphMlReader<N, E, Ty = Directed, Ix = petgraph::graph::DefaultIx> {
+    node_weight: Box<NodeWeightFn<N>>,
+    edge_weight: Box<EdgeWeightFn<E>>,
+    _ty: std::marker::PhantomData<(Ty, Ix)>,
+}
+
+impl<N, E, Ty, Ix> fmt::Debug for GraphMlReader<N, E, Ty, Ix> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GraphMlReader").finish()
+    }
+}
+
+impl<N, E, Ty, Ix> GraphMlReader<N, E, Ty, Ix>
+where
+    Ty: EdgeType,
+    Ix: IndexType,
+{
+    /// Create a new GraphML reader.
+    ///
+    /// `node_weight` and `edge_weight` convert the attributes collected for a `<node>` or
+    /// `<edge>` element into the graph's weight types.
+    pub fn new(node_weight: Box<NodeWeightFn<N>>, edge_weight: Box<EdgeWeightFn<E>>) -> Self {
+        Self {
+            node_weight,
+            edge_weight,
+            _ty: std::marker::PhantomData,
+        }
+    }
+
+    /// Parse a GraphML document from a string.
+    pub fn from_str(&self, input: &str) -> Result<Graph<N, E, Ty, Ix>, GraphMlError> {
+        self.from_reader(input.as_bytes())
+    }
+
+    /// Parse a GraphML document from a reader.
+    pub fn from_reader<R>(&self, input: R) -> Result<Graph<N, E, Ty, Ix>, GraphMlError>
+    where
+        R: Read,
+    {
+        let parser = EventReader::new(input);
+        let mut keys: HashMap<String, KeyDecl> = HashMap::new();
+        let mut graph = Graph::<N, E, Ty, Ix>::with_capacity(0, 0);
+        let mut node_indices: HashMap<String, NodeIndex<Ix>> = HashMap::new();
+        let mut pending_nodes: Vec<(String, HashMap<String, String>)> = Vec::new();
+        let mut pending_edges: Vec<(String, String, HashMap<String, String>)> = Vec::new();
+
+        let mut current_key: Option<String> = None;
+        let mut current_node: Option<(String, HashMap<String, String>)> = None;
+        let mut current_edge: Option<(String, String, HashMap<String, String>)> = None;
+        let mut current_data_key: Option<String> = None;
+        let mut text = String::new();
+
+        for event in parser {
+            match event? {
+                XmlEvent::StartElement {
+                    name, attributes, ..
+                } => {
+                    let attrs: HashMap<String, String> = attributes
+                        .into_iter()
+                        .map(|attr| (attr.name.local_name, attr.value))
+                        .collect();
+                    match name.local_name.as_str() {
+                        "key" => {
+                            let id =
+                                attrs
+                                    .get("id")
+                                    .cloned()
+                                    .ok_or(GraphMlError::MissingAttribute {
+                                        element: "key",
+                                        attribute: "id",
+                                    })?;
+                            let for_ =
+                                KeyFor::parse(attrs.get("for").map_or("all", String::as_str))?;
+                            let attr_name = attrs
+                                .get("attr.name")
+                                .cloned()
+                                .unwrap_or_else(|| id.clone());
+                            let attr_type = match attrs.get("attr.type").map(String::as_str) {
+                                Some("boolean") => AttrType::Boolean,
+                                Some("int") => AttrType::Int,
+                                Some("long") => AttrType::Long,
+                                Some("float") => AttrType::Float,
+                                Some("double") => AttrType::Double,
+                                _ => AttrType::String,
+                            };
+                            keys.insert(
+                                id.clone(),
+                                KeyDecl {
+                                    for_,
+                                    attr_name,
+                                    attr_type,
+                                    default: None,
+                                },
+                            );
+                            current_key = Some(id);
+                        }
+                        "default" => {
+                            text.clear();
+                        }
+                        "graph" => {
+                            let directed =
+                                attrs.get("edgedefault").is_none_or(|v| v != "undirected");
+                            if directed != Ty::is_directed() {
+                                return Err(GraphMlError::EdgeDefaultMismatch {
+                                    expected: if Ty::is_directed() {
+                                        "directed"
+                                    } else {
+                                        "undirected"
+                                    },
+                                    found: if directed { "directed" } else { "undirected" },
+                                });
+                            }
+                        }
+                        "node" => {
+                            let id =
+                                attrs
+                                    .get("id")
+                                    .cloned()
+                                    .ok_or(GraphMlError::MissingAttribute {
+                                        element: "node",
+                                        attribute: "id",
+                                    })?;
+                            current_node = Some((id, HashMap::new()));
+                        }
+                        "edge" => {
+                            let source = attrs.get("source").cloned().ok_or(
+                                GraphMlError::MissingAttribute {
+                                    element: "edge",
+                                    attribute: "source",
+                                },
+                            )?;
+                            let target = attrs.get("target").cloned().ok_or(
+                                GraphMlError::MissingAttribute {
+                                    element: "edge",
+                                    attribute: "target",
+                                },
+                            )?;
+                            current_edge = Some((source, target, HashMap::new()));
+                        }
+                        "data" => {
+                            let key = attrs.get("key").cloned().ok_or(
+                                GraphMlError::MissingAttribute {
+                                    element: "data",
+                                    attribute: "key",
+                                },
+                            )?;
+                            current_data_key = Some(key);
+                            text.clear();
+                        }
+                        _ => {}
+                    }
+                }
+                XmlEvent::Characters(s) | XmlEvent::CData(s) => {
+                    text.push_str(&s);
+                }
+                XmlEvent::EndElement { name } => match name.local_name.as_str() {
+                    "default" => {
+                        if let Some(id) = &current_key {
+                            if let Some(decl) = keys.get_mut(id) {
+                                decl.default = Some(text.clone());
+                            }
+                        }
+                        text.clear();
+                    }
+                    "key" => {
+                        current_key = None;
+                    }
+                    "data" => {
+                        if let Some(key) = current_data_key.take() {
+                            if let Some((_, data)) = current_node.as_mut() {
+                                data.insert(key, text.clone());
+                            } else if let Some((_, _, data)) = current_edge.as_mut() {
+                                data.insert(key, text.clone());
+                            }
+                        }
+                        text.clear();
+                    }
+                    "node" => {
+                        if let Some((id, data)) = current_node.take() {
+                            pending_nodes.push((id, data));
+                        }
+                    }
+                    "edge" => {
+                        if let Some((source, target, data)) = current_edge.take() {
+                            pending_edges.push((source, target, data));
+                        }
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+
+        // `<key>` declarations can appear anywhere in the document — in particular, `GraphMl`
+        // itself emits them after `</graph>` — so nodes and edges are only resolved against
+        // `keys` once the whole document has been read.
+        for (id, data) in pending_nodes {
+            let resolved = Self::resolve(&keys, &data, KeyFor::Node)?;
+            let idx = graph.add_node((self.node_weight)(&resolved));
+            node_indices.insert(id, idx);
+        }
+
+        for (source, target, data) in pending_edges {
+            let source_idx = *node_indices
+                .get(&source)
+                .ok_or_else(|| GraphMlError::UnknownNode(source.clone()))?;
+            let target_idx = *node_indices
+                .get(&target)
+                .ok_or_else(|| GraphMlError::UnknownNode(target.clone()))?;
+            let resolved = Self::resolve(&keys, &data, KeyFor::Edge)?;
+            graph.add_edge(source_idx, target_idx, (self.edge_weight)(&resolved));
+        }
+
+        Ok(graph)
+    }
+
+    /// Resolve the raw `key id -> value` map collected for a node/edge into an
+    /// `attr.name -> value` map, filling in `<default>` values for keys that apply to `scope`
+    /// but were not present on this particular element.
+    fn resolve(
+        keys: &HashMap<String, KeyDecl>,
+        data: &HashMap<String, String>,
+        scope: KeyFor,
+    ) -> Result<HashMap<String, String>, GraphMlError> {
+        let mut resolved = HashMap::with_capacity(data.len());
+        for (key_id, value) in data {
+            let decl = keys
+                .get(key_id)
+                .ok_or_else(|| GraphMlError::UnknownKey(key_id.clone()))?;
+            resolved.insert(decl.attr_name.clone(), value.clone());
+        }
+        for decl in keys.values() {
+            if decl.for_.applies_to(scope) && !resolved.contains_key(&decl.attr_name) {
+                if let Some(default) = &decl.default {
+                    resolved.insert(decl.attr_name.clone(), default.clone());
+                }
+            }
+        }
+        Ok(resolved)
+    }
+}
@@ -0,0 +1,48 @@
+use petgraph::graph::Graph;
+use petgraph::visit::NodeRef;
+use petgraph_graphml::GraphMl;
+
+#[test]
+fn custom_node_and_edge_ids() {
+    let mut deps = Graph::<&str, &str>::new();
+    let pg = deps.add_node("petgraph");
+    let fb = deps.add_node("fixedbitset");
+    deps.update_edge(pg, fb, "depends on");
+
+    let graphml = GraphMl::new(&deps)
+        .pretty_print(true)
+        .node_id(Box::new(|node| (*node.weight()).into()))
+        .edge_id(Box::new(|edge| (*edge.weight()).into()));
+    let xml = graphml.to_string();
+    let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <graph edgedefault="directed">
+    <node id="petgraph"/>
+    <node id="fixedbitset"/>
+    <edge id="depends_on" source="petgraph" target="fixedbitset"/>
+  </graph>
+</graphml>"#;
+
+    assert_eq!(expected, xml);
+}
+
+#[test]
+fn colliding_custom_ids_are_deduplicated() {
+    let mut deps = Graph::<&str, ()>::new();
+    deps.add_node("same");
+    deps.add_node("same");
+
+    let graphml = GraphMl::new(&deps)
+        .pretty_print(true)
+        .node_id(Box::new(|node| (*node.weight()).into()));
+    let xml = graphml.to_string();
+    let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <graph edgedefault="directed">
+    <node id="same"/>
+    <node id="same_1"/>
+  </graph>
+</graphml>"#;
+
+    assert_eq!(expected, xml);
+}
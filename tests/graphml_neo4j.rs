@@ -0,0 +1,40 @@
+use petgraph::graph::Graph;
+use petgraph::visit::NodeRef;
+use petgraph_graphml::GraphMl;
+
+#[test]
+fn node_labels_and_edge_type() {
+    let mut deps = Graph::<&str, &str>::new();
+    let pg = deps.add_node("petgraph");
+    let fb = deps.add_node("fixedbitset");
+    deps.update_edge(pg, fb, "depends on");
+
+    let graphml = GraphMl::new(&deps)
+        .pretty_print(true)
+        .node_labels(Box::new(|node| {
+            vec!["Crate".to_string(), node.weight().to_string()]
+        }))
+        .edge_type(Box::new(|_| "DEPENDS_ON".to_string()));
+    let xml = graphml.to_string();
+    let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <graph edgedefault="directed">
+    <node id="n0">
+      <data key="labels">:Crate:petgraph</data>
+    </node>
+    <node id="n1">
+      <data key="labels">:Crate:fixedbitset</data>
+    </node>
+    <edge id="e0" source="n0" target="n1">
+      <data key="label">DEPENDS_ON</data>
+    </edge>
+  </graph>
+  <key id="labels" for="node" attr.name="labels" attr.type="string"/>
+  <key id="label" for="edge" attr.name="label" attr.type="string"/>
+</graphml>"#;
+
+    // HashSet output is unordered, therefore we do not know the order of the keys
+    assert!(xml.starts_with(&expected[..expected.find("<key").unwrap()]));
+    assert!(xml.contains(r#"<key id="labels" for="node" attr.name="labels" attr.type="string"/>"#));
+    assert!(xml.contains(r#"<key id="label" for="edge" attr.name="label" attr.type="string"/>"#));
+}
@@ -0,0 +1,64 @@
+use petgraph::graph::Graph;
+use petgraph_graphml::{GraphMl, GraphMlError, GraphMlReader};
+use std::collections::HashMap;
+
+fn weight_from_data(data: &HashMap<String, String>) -> String {
+    data.get("weight").cloned().unwrap_or_default()
+}
+
+#[test]
+fn round_trips_a_simple_graph() {
+    let mut deps = Graph::<&str, &str>::new();
+    let pg = deps.add_node("petgraph");
+    let fb = deps.add_node("fixedbitset");
+    deps.update_edge(pg, fb, "depends on");
+
+    let xml = GraphMl::new(&deps)
+        .pretty_print(true)
+        .export_node_weights_display()
+        .export_edge_weights_display()
+        .to_string();
+
+    let reader = GraphMlReader::new(Box::new(weight_from_data), Box::new(weight_from_data));
+    let parsed: Graph<String, String> = reader.from_str(&xml).unwrap();
+
+    assert_eq!(parsed.node_count(), 2);
+    assert_eq!(parsed.edge_count(), 1);
+    let weights: Vec<_> = parsed.node_weights().cloned().collect();
+    assert!(weights.contains(&"petgraph".to_string()));
+    assert!(weights.contains(&"fixedbitset".to_string()));
+    assert_eq!(parsed.edge_weights().next().unwrap(), "depends on");
+}
+
+#[test]
+fn dangling_edge_endpoint_is_an_error() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <graph edgedefault="directed">
+    <node id="n0" />
+    <edge id="e0" source="n0" target="n1" />
+  </graph>
+</graphml>"#;
+
+    let reader: GraphMlReader<(), ()> = GraphMlReader::new(Box::new(|_| ()), Box::new(|_| ()));
+    let err = reader.from_str(xml).unwrap_err();
+    assert!(matches!(err, GraphMlError::UnknownNode(ref id) if id == "n1"));
+}
+
+#[test]
+fn key_defaults_are_applied_when_data_is_missing() {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="weight" for="node" attr.name="weight" attr.type="string">
+    <default>unknown</default>
+  </key>
+  <graph edgedefault="directed">
+    <node id="n0" />
+  </graph>
+</graphml>"#;
+
+    let reader = GraphMlReader::new(Box::new(weight_from_data), Box::new(weight_from_data));
+    let parsed: Graph<String, String> = reader.from_str(xml).unwrap();
+
+    assert_eq!(parsed.node_weights().next().unwrap(), "unknown");
+}